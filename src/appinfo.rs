@@ -0,0 +1,239 @@
+//! Offline parser for Steam's `appcache/appinfo.vdf`.
+//!
+//! This lets us resolve appid -> name for every app Steam already knows
+//! about locally, without round-tripping to the Store API.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const MAGIC_27: u32 = 0x07564427;
+const MAGIC_28: u32 = 0x07564428;
+const MAGIC_29: u32 = 0x07564429;
+
+/// A parsed binary KeyValues node.
+#[derive(Debug)]
+#[allow(dead_code)]
+enum KeyValue {
+    Object(Vec<(String, KeyValue)>),
+    Str(String),
+    Int32(i32),
+    UInt64(u64),
+}
+
+impl KeyValue {
+    fn get(&self, key: &str) -> Option<&KeyValue> {
+        match self {
+            KeyValue::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            KeyValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Cursor { data, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        let byte = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(self.pos..self.pos + 4)?.try_into().ok()?;
+        self.pos += 4;
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    fn read_i32(&mut self) -> Option<i32> {
+        self.read_u32().map(|v| v as i32)
+    }
+
+    fn read_u64(&mut self) -> Option<u64> {
+        let bytes: [u8; 8] = self.data.get(self.pos..self.pos + 8)?.try_into().ok()?;
+        self.pos += 8;
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        if self.remaining() < n {
+            return None;
+        }
+        self.pos += n;
+        Some(())
+    }
+
+    fn read_cstring(&mut self) -> Option<String> {
+        let start = self.pos;
+        let nul_offset = self.data[start..].iter().position(|&b| b == 0)?;
+        let s = String::from_utf8_lossy(&self.data[start..start + nul_offset]).into_owned();
+        self.pos = start + nul_offset + 1;
+        Some(s)
+    }
+
+    /// Parses entries until the `0x08` end-of-object marker is consumed.
+    fn read_object(&mut self) -> Option<KeyValue> {
+        let mut entries = Vec::new();
+
+        loop {
+            let entry_type = self.read_u8()?;
+            if entry_type == 0x08 {
+                return Some(KeyValue::Object(entries));
+            }
+
+            let key = self.read_cstring()?;
+            let value = match entry_type {
+                0x00 => self.read_object()?,
+                0x01 => KeyValue::Str(self.read_cstring()?),
+                0x02 => KeyValue::Int32(self.read_i32()?),
+                0x07 => KeyValue::UInt64(self.read_u64()?),
+                _ => return None,
+            };
+
+            entries.push((key, value));
+        }
+    }
+}
+
+/// Reads `appcache/appinfo.vdf` under `steam_path` and returns a map of
+/// appid -> display name for every entry it could resolve. Returns an
+/// empty map if the file is missing or unparseable so callers can fall
+/// back to the network resolver on a miss.
+pub fn load_appinfo_cache(steam_path: &Path) -> HashMap<u32, String> {
+    let mut names = HashMap::new();
+
+    let data = match fs::read(steam_path.join("appcache/appinfo.vdf")) {
+        Ok(data) => data,
+        Err(_) => return names,
+    };
+
+    let mut cursor = Cursor::new(&data);
+
+    let magic = match cursor.read_u32() {
+        Some(magic) if magic == MAGIC_27 || magic == MAGIC_28 || magic == MAGIC_29 => magic,
+        _ => return names,
+    };
+
+    if cursor.read_u32().is_none() {
+        // Universe field.
+        return names;
+    }
+
+    while let Some(app_id) = cursor.read_u32() {
+        if app_id == 0 {
+            break;
+        }
+
+        let size = match cursor.read_u32() {
+            Some(size) => size as usize,
+            None => break,
+        };
+        let entry_end = cursor.pos + size;
+
+        let parsed = (|| {
+            cursor.read_u32()?; // info_state
+            cursor.read_u32()?; // last_updated
+            cursor.read_u64()?; // pics_token
+            cursor.skip(20)?; // text_vdf_sha1
+            cursor.read_u32()?; // change_number
+            if magic == MAGIC_29 {
+                cursor.skip(20)?; // binary_vdf_sha1
+            }
+            cursor.read_object()
+        })();
+
+        if let Some(kv) = parsed {
+            if let Some(name) = kv
+                .get("common")
+                .and_then(|c| c.get("name"))
+                .and_then(|n| n.as_str())
+            {
+                names.insert(app_id, name.to_string());
+            }
+        }
+
+        if entry_end < cursor.pos || entry_end > data.len() {
+            break;
+        }
+        cursor.pos = entry_end;
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cstr(s: &str) -> Vec<u8> {
+        let mut v = s.as_bytes().to_vec();
+        v.push(0);
+        v
+    }
+
+    #[test]
+    fn parses_name_from_synthetic_appinfo_vdf() {
+        let mut object = Vec::new();
+        object.push(0x00); // "common" is itself an object
+        object.extend(cstr("common"));
+        object.push(0x01); // "name" is a string
+        object.extend(cstr("name"));
+        object.extend(cstr("Test Game"));
+        object.push(0x08); // end of "common"
+        object.push(0x08); // end of the entry's root object
+
+        let mut entry_body = Vec::new();
+        entry_body.extend(0u32.to_le_bytes()); // info_state
+        entry_body.extend(0u32.to_le_bytes()); // last_updated
+        entry_body.extend(0u64.to_le_bytes()); // pics_token
+        entry_body.extend([0u8; 20]); // text_vdf_sha1
+        entry_body.extend(0u32.to_le_bytes()); // change_number
+        entry_body.extend(&object);
+
+        let mut data = Vec::new();
+        data.extend(MAGIC_28.to_le_bytes());
+        data.extend(0u32.to_le_bytes()); // universe
+        data.extend(440u32.to_le_bytes()); // app_id
+        data.extend((entry_body.len() as u32).to_le_bytes()); // entry size
+        data.extend(&entry_body);
+        data.extend(0u32.to_le_bytes()); // terminating app_id
+
+        let dir = std::env::temp_dir().join(format!("appinfo_test_{}_{}", std::process::id(), 1));
+        fs::create_dir_all(dir.join("appcache")).unwrap();
+        fs::write(dir.join("appcache/appinfo.vdf"), &data).unwrap();
+
+        let names = load_appinfo_cache(&dir);
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(names.get(&440), Some(&"Test Game".to_string()));
+    }
+
+    #[test]
+    fn returns_empty_map_when_file_is_missing() {
+        let dir = std::env::temp_dir().join(format!("appinfo_test_{}_{}", std::process::id(), 2));
+        let names = load_appinfo_cache(&dir);
+        assert!(names.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Backup and restore of compatdata prefixes as compressed tarballs.
+//!
+//! Each archive is a gzip-compressed tar containing the prefix's full
+//! directory tree plus a `manifest.json` recording the appid, resolved
+//! name, and detected compat tool, so a backup is self-describing and can
+//! be moved between libraries or machines.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const MANIFEST_NAME: &str = "manifest.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub app_id: u32,
+    pub name: String,
+    pub compat_tool: String,
+}
+
+/// Archives `prefix_path` (a `steamapps/compatdata/<id>/` directory) into
+/// `<dest_dir>/<app_id>.tar.gz`, embedding `manifest` at the archive root.
+/// Returns the path to the created archive.
+pub fn backup_prefix(
+    prefix_path: &Path,
+    manifest: &BackupManifest,
+    dest_dir: &Path,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dest_dir)?;
+    let archive_path = dest_dir.join(format!("{}.tar.gz", manifest.app_id));
+
+    let archive_file = File::create(&archive_path)?;
+    let encoder = GzEncoder::new(archive_file, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+    // Wine/Proton prefixes always contain dosdevices/z: (and usually c:)
+    // symlinked back into the filesystem (often all the way to `/`), plus
+    // XDG dirs symlinked into the real home directory. Without this, the
+    // default `follow_symlinks(true)` would dereference them and archive
+    // the entire filesystem they point at.
+    builder.follow_symlinks(false);
+    builder.append_dir_all("prefix", prefix_path)?;
+    builder.into_inner()?.finish()?;
+
+    Ok(archive_path)
+}
+
+/// Extracts an archive created by `backup_prefix` back into
+/// `compatdata_root/<app_id>/`, re-creating the prefix directory. Returns
+/// the restored manifest, or `None` if the user declined to overwrite an
+/// existing prefix.
+pub fn restore_prefix(
+    archive_path: &Path,
+    compatdata_root: &Path,
+) -> io::Result<Option<BackupManifest>> {
+    let archive_file = File::open(archive_path)?;
+    let decoder = GzDecoder::new(archive_file);
+    let mut archive = tar::Archive::new(decoder);
+
+    let extract_dir = compatdata_root.join(".restore-tmp");
+    fs::create_dir_all(&extract_dir)?;
+    archive.unpack(&extract_dir)?;
+
+    let manifest_content = fs::read_to_string(extract_dir.join(MANIFEST_NAME))?;
+    let manifest: BackupManifest = serde_json::from_str(&manifest_content)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let dest = compatdata_root.join(manifest.app_id.to_string());
+    if dest.exists() {
+        print!(
+            "\nAppID {}'s compatdata prefix already exists at {}. Overwrite it? [y/N] ",
+            manifest.app_id,
+            dest.display()
+        );
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            fs::remove_dir_all(&extract_dir)?;
+            return Ok(None);
+        }
+
+        fs::remove_dir_all(&dest)?;
+    }
+    fs::rename(extract_dir.join("prefix"), &dest)?;
+    fs::remove_dir_all(&extract_dir)?;
+
+    Ok(Some(manifest))
+}
@@ -0,0 +1,206 @@
+//! Orphaned compatdata prefix cleanup: finds prefixes whose game is no
+//! longer installed, reports how much disk space they hold, and optionally
+//! deletes them.
+
+use colored::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::backup::{self, BackupManifest};
+use crate::CompatData;
+
+/// CLI flags controlling the cleanup mode.
+#[derive(Debug, Default)]
+pub struct CliOptions {
+    /// `--prune`: find and offer to delete orphaned prefixes.
+    pub prune: bool,
+    /// `--dry-run`: report what would be deleted without deleting anything.
+    pub dry_run: bool,
+    /// `--backup-dir <path>`: archive each orphan here before deleting it.
+    pub backup_dir: Option<PathBuf>,
+}
+
+/// Parses cleanup-related flags out of the process's command-line
+/// arguments.
+pub fn parse_args() -> CliOptions {
+    let mut options = CliOptions::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--prune" => options.prune = true,
+            "--dry-run" => options.dry_run = true,
+            "--backup-dir" => options.backup_dir = args.next().map(PathBuf::from),
+            _ => {}
+        }
+    }
+
+    options
+}
+
+/// A compatdata prefix with no corresponding installed app.
+pub struct OrphanedPrefix {
+    pub app_id: u32,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub name: String,
+    pub compat_tool: String,
+}
+
+/// Finds compatdata entries whose appid is neither an installed app nor a
+/// known Proton tool, and computes their on-disk size.
+pub fn find_orphaned_prefixes(
+    all_compatdata: &[CompatData],
+    all_installed_apps: &HashSet<u32>,
+    proton_app_ids: &HashSet<u32>,
+    resolved_names: &HashMap<u32, (bool, String)>,
+    compat_tools: &HashMap<u32, String>,
+) -> Vec<OrphanedPrefix> {
+    all_compatdata
+        .iter()
+        .filter(|entry| {
+            !all_installed_apps.contains(&entry.app_id) && !proton_app_ids.contains(&entry.app_id)
+        })
+        .map(|entry| OrphanedPrefix {
+            app_id: entry.app_id,
+            path: entry.path.clone(),
+            size_bytes: dir_size(&entry.path),
+            name: resolved_names
+                .get(&entry.app_id)
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| "Unknown Application".to_string()),
+            compat_tool: compat_tools
+                .get(&entry.app_id)
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+        })
+        .collect()
+}
+
+/// Recursively walks a directory and sums the size of every file in it.
+pub fn dir_size(path: &std::path::Path) -> u64 {
+    let mut total = 0u64;
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+
+        if metadata.is_dir() {
+            total += dir_size(&entry.path());
+        } else {
+            total += metadata.len();
+        }
+    }
+
+    total
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.2} {}", size, UNITS[unit])
+}
+
+/// Prints a table of orphaned prefixes and the total reclaimable space.
+pub fn print_orphan_report(orphans: &[OrphanedPrefix]) {
+    println!("\n{}", "Orphaned Compatdata Prefixes:".bold().red());
+    println!("{}", "=============================".bold());
+
+    if orphans.is_empty() {
+        println!("No orphaned prefixes found.");
+        return;
+    }
+
+    let mut total_bytes = 0u64;
+    for orphan in orphans {
+        total_bytes += orphan.size_bytes;
+        println!(
+            "AppID {:6} | {:>10} | {}",
+            orphan.app_id.to_string().blue(),
+            human_size(orphan.size_bytes),
+            orphan.path.display()
+        );
+    }
+
+    println!(
+        "\n{} {}",
+        "Total reclaimable space:".bold(),
+        human_size(total_bytes).green()
+    );
+}
+
+/// Deletes the given orphaned prefixes after an interactive confirmation.
+/// When `backup_dir` is set, each prefix is archived there first so a
+/// pruned prefix can later be restored with [`crate::backup::restore_prefix`].
+pub fn delete_orphans(orphans: &[OrphanedPrefix], backup_dir: Option<&PathBuf>) -> io::Result<()> {
+    if orphans.is_empty() {
+        return Ok(());
+    }
+
+    print!(
+        "\nDelete {} orphaned prefix(es) listed above? [y/N] ",
+        orphans.len()
+    );
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    if !answer.trim().eq_ignore_ascii_case("y") {
+        println!("Aborted, no prefixes were deleted.");
+        return Ok(());
+    }
+
+    for orphan in orphans {
+        if let Some(backup_dir) = backup_dir {
+            let manifest = BackupManifest {
+                app_id: orphan.app_id,
+                name: orphan.name.clone(),
+                compat_tool: orphan.compat_tool.clone(),
+            };
+
+            match backup::backup_prefix(&orphan.path, &manifest, backup_dir) {
+                Ok(archive_path) => {
+                    println!("Backed up to {}", archive_path.display().to_string().cyan())
+                }
+                Err(err) => {
+                    println!(
+                        "{} {}: {}",
+                        "Failed to back up".red(),
+                        orphan.path.display(),
+                        err
+                    );
+                    continue;
+                }
+            }
+        }
+
+        match fs::remove_dir_all(&orphan.path) {
+            Ok(()) => println!("Deleted {}", orphan.path.display().to_string().green()),
+            Err(err) => println!(
+                "{} {}: {}",
+                "Failed to delete".red(),
+                orphan.path.display(),
+                err
+            ),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Detection of the Proton/compat tool each game is actually configured to
+//! use, read from Steam's own config files rather than a hardcoded table.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Parses the `CompatToolMapping` section of the global `config/config.vdf`
+/// and returns a map of appid -> tool name (e.g. `proton_experimental`,
+/// `GE-Proton9-20`, `proton_9`).
+pub fn parse_compat_tool_mapping(config_vdf_path: &Path) -> HashMap<u32, String> {
+    let mut mapping = HashMap::new();
+
+    let content = match fs::read_to_string(config_vdf_path) {
+        Ok(content) => content,
+        Err(_) => return mapping,
+    };
+
+    let mut in_mapping_section = false;
+    let mut mapping_depth = 0i32;
+    let mut current_app_id: Option<u32> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if !in_mapping_section {
+            if trimmed.eq_ignore_ascii_case("\"CompatToolMapping\"") {
+                in_mapping_section = true;
+            }
+            continue;
+        }
+
+        if trimmed == "{" {
+            mapping_depth += 1;
+            continue;
+        }
+
+        if trimmed == "}" {
+            mapping_depth -= 1;
+            if mapping_depth <= 1 {
+                current_app_id = None;
+            }
+            if mapping_depth <= 0 {
+                // This is CompatToolMapping's own closing brace (or we
+                // somehow over-closed): leave the section so sibling
+                // blocks in config.vdf aren't mistaken for appid entries.
+                in_mapping_section = false;
+                mapping_depth = 0;
+            }
+            continue;
+        }
+
+        // Inside the mapping section but not yet inside a per-app block: the
+        // next quoted token is the appid key that opens one.
+        if mapping_depth == 1 && current_app_id.is_none() {
+            if let Some(app_id_str) = trimmed.split('"').nth(1) {
+                current_app_id = app_id_str.parse::<u32>().ok();
+            }
+            continue;
+        }
+
+        if mapping_depth == 2 {
+            if let Some(app_id) = current_app_id {
+                let mut parts = trimmed.split('"').filter(|s| !s.trim().is_empty());
+                if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+                    if key.eq_ignore_ascii_case("name") && !value.is_empty() {
+                        mapping.insert(app_id, value.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    mapping
+}
+
+/// Reads the per-prefix `steamapps/compatdata/<id>/config_info` override, if
+/// present. This takes precedence over the global mapping for that prefix.
+pub fn read_prefix_config_info(compatdata_entry_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(compatdata_entry_path.join("config_info")).ok()?;
+    let tool = content.trim();
+
+    if tool.is_empty() {
+        None
+    } else {
+        Some(tool.to_string())
+    }
+}
+
+/// Discovers custom compat tools installed under `compatibilitytools.d/`
+/// (e.g. GE-Proton builds), keyed by their internal tool name.
+pub fn discover_custom_tools(steam_path: &Path) -> HashMap<String, String> {
+    let mut tools = HashMap::new();
+
+    let tools_dir = steam_path.join("compatibilitytools.d");
+    let entries = match fs::read_dir(&tools_dir) {
+        Ok(entries) => entries,
+        Err(_) => return tools,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let tool_path = entry.path();
+        if !tool_path.is_dir() {
+            continue;
+        }
+
+        let vdf_path = tool_path.join("compatibilitytool.vdf");
+        let (internal_name, display_name) = match parse_compatibilitytool_vdf(&vdf_path) {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+
+        tools.insert(internal_name, display_name);
+    }
+
+    tools
+}
+
+/// Wrapper keys every `compatibilitytool.vdf` nests its tools under before
+/// reaching the tool's own name, e.g. `"compatibilitytools" { "compat_tools"
+/// { "GE-Proton9-20" { ... } } }`.
+const COMPATIBILITYTOOL_WRAPPER_KEYS: [&str; 2] = ["compatibilitytools", "compat_tools"];
+
+fn parse_compatibilitytool_vdf(path: &Path) -> Option<(String, String)> {
+    let content = fs::read_to_string(path).ok()?;
+
+    let mut pending_key: Option<String> = None;
+    let mut internal_name = None;
+    let mut display_name = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed == "{" {
+            // The standalone key seen just before this brace is the name of
+            // the block it opens. The first one that isn't a known wrapper
+            // is the tool's own internal name.
+            if let Some(key) = pending_key.take() {
+                if internal_name.is_none()
+                    && !COMPATIBILITYTOOL_WRAPPER_KEYS.contains(&key.as_str())
+                {
+                    internal_name = Some(key);
+                }
+            }
+            continue;
+        }
+
+        if trimmed == "}" {
+            continue;
+        }
+
+        let mut parts = trimmed.split('"').filter(|s| !s.trim().is_empty());
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => {
+                pending_key = None;
+                if key.eq_ignore_ascii_case("display_name") {
+                    display_name = Some(value.to_string());
+                }
+            }
+            (Some(key), None) => {
+                // A standalone quoted key: either it opens a block (the next
+                // line is "{") or it's mid-key-value across two lines, which
+                // this format doesn't use, so treat it as a pending block key.
+                pending_key = Some(key.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let internal_name = internal_name?;
+    let display_name = display_name.unwrap_or_else(|| internal_name.clone());
+    Some((internal_name, display_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("compattool_test_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn parses_every_appid_in_the_mapping() {
+        let path = temp_path("multi_appid.vdf");
+        fs::write(
+            &path,
+            r#""Steam"
+{
+    "CompatToolMapping"
+    {
+        "1245620"
+        {
+            "name"      "proton_experimental"
+            "config"        ""
+            "priority"      "250"
+        }
+        "1091500"
+        {
+            "name"      "GE-Proton9-20"
+            "config"        ""
+            "priority"      "250"
+        }
+        "730"
+        {
+            "name"      "proton_9"
+            "config"        ""
+            "priority"      "250"
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let mapping = parse_compat_tool_mapping(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(mapping.get(&1245620), Some(&"proton_experimental".to_string()));
+        assert_eq!(mapping.get(&1091500), Some(&"GE-Proton9-20".to_string()));
+        assert_eq!(mapping.get(&730), Some(&"proton_9".to_string()));
+        assert_eq!(mapping.len(), 3);
+    }
+
+    #[test]
+    fn stops_at_the_mapping_sections_own_closing_brace() {
+        // A sibling section with numeric keys that look like appids must
+        // never be picked up once CompatToolMapping itself has closed.
+        let path = temp_path("sibling_section.vdf");
+        fs::write(
+            &path,
+            r#""Steam"
+{
+    "CompatToolMapping"
+    {
+        "1245620"
+        {
+            "name"      "proton_experimental"
+        }
+    }
+    "Apps"
+    {
+        "9999999"
+        {
+            "name"      "not_a_compat_tool"
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let mapping = parse_compat_tool_mapping(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(mapping.get(&1245620), Some(&"proton_experimental".to_string()));
+        assert!(!mapping.contains_key(&9999999));
+        assert_eq!(mapping.len(), 1);
+    }
+
+    #[test]
+    fn parses_internal_name_from_compatibilitytool_vdf() {
+        let path = temp_path("compatibilitytool.vdf");
+        fs::write(
+            &path,
+            r#""compatibilitytools"
+{
+    "compat_tools"
+    {
+        "GE-Proton9-20"
+        {
+            "install_path" "."
+            "display_name" "GE-Proton9-20"
+
+            "from_oslist"  "windows"
+            "to_oslist"    "linux"
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let parsed = parse_compatibilitytool_vdf(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(
+            parsed,
+            Some(("GE-Proton9-20".to_string(), "GE-Proton9-20".to_string()))
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Bounded-concurrency app info fetching with an on-disk, TTL'd cache.
+//!
+//! Replaces the old "await one fetch, sleep 200ms, repeat" loop with a
+//! `buffer_unordered` pipeline so many appids can be resolved in parallel
+//! while a simple token-bucket rate limiter still keeps us under Steam's
+//! limits. Anything resolved is written to `~/.cache/steam-compatdata-checker/`
+//! so later runs don't re-fetch it until it goes stale.
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+
+/// How long a cached entry is trusted before it's re-fetched.
+const CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Max fetches per second, enforced by `RateLimiter`.
+const RATE_LIMIT_PER_SEC: u64 = 4;
+
+/// How many fetches may be in flight at once.
+const DEFAULT_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CachedEntry {
+    success: bool,
+    name: String,
+    fetched_at: u64,
+}
+
+/// JSON-backed cache of resolved appids, stored under
+/// `~/.cache/steam-compatdata-checker/appinfo_cache.json`.
+pub struct DiskCache {
+    path: PathBuf,
+    entries: HashMap<u32, CachedEntry>,
+}
+
+impl DiskCache {
+    pub fn load() -> Self {
+        let path = cache_file_path();
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        DiskCache { path, entries }
+    }
+
+    fn get_fresh(&self, app_id: u32) -> Option<(bool, String)> {
+        let entry = self.entries.get(&app_id)?;
+        let now = unix_now();
+
+        if now.saturating_sub(entry.fetched_at) > CACHE_TTL_SECS {
+            return None;
+        }
+
+        Some((entry.success, entry.name.clone()))
+    }
+
+    fn insert(&mut self, app_id: u32, success: bool, name: String) {
+        self.entries.insert(
+            app_id,
+            CachedEntry {
+                success,
+                name,
+                fetched_at: unix_now(),
+            },
+        );
+    }
+
+    pub fn save(&self) {
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Ok(json) = serde_json::to_string_pretty(&self.entries) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+}
+
+fn cache_file_path() -> PathBuf {
+    let base = std::env::var("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("."));
+
+    base.join(".cache/steam-compatdata-checker/appinfo_cache.json")
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A simple token-bucket limiter shared across the concurrent fetchers so
+/// we don't hammer the Store API even with a high `buffer_unordered` width.
+struct RateLimiter {
+    interval: Duration,
+    last_fetch: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(per_second: u64) -> Self {
+        RateLimiter {
+            interval: Duration::from_millis(1000 / per_second.max(1)),
+            last_fetch: Mutex::new(Instant::now() - Duration::from_secs(1)),
+        }
+    }
+
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut last_fetch = self.last_fetch.lock().unwrap();
+                let now = Instant::now();
+                let earliest = *last_fetch + self.interval;
+
+                if now >= earliest {
+                    *last_fetch = now;
+                    None
+                } else {
+                    Some(earliest - now)
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(duration) => tokio::time::sleep(duration).await,
+            }
+        }
+    }
+}
+
+async fn fetch_one(app_id: u32, limiter: &RateLimiter) -> (u32, Option<(bool, String)>) {
+    limiter.acquire().await;
+
+    let url = format!(
+        "https://store.steampowered.com/api/appdetails?appids={}",
+        app_id
+    );
+
+    let result = match reqwest::get(&url).await {
+        Ok(response) => match response.json::<serde_json::Value>().await {
+            Ok(json) => json.get(app_id.to_string()).map(|app_data| {
+                let success = app_data["success"].as_bool().unwrap_or(false);
+                let name = if success {
+                    app_data["data"]["name"]
+                        .as_str()
+                        .unwrap_or("Unknown")
+                        .to_string()
+                } else {
+                    "Unknown Application".to_string()
+                };
+                (success, name)
+            }),
+            Err(_) => None,
+        },
+        Err(_) => None,
+    };
+
+    (app_id, result)
+}
+
+/// Resolves `app_ids` to `(success, name)` pairs, serving cache hits from
+/// `appinfo_cache` (the local appinfo.vdf dump) and `disk_cache` first, and
+/// fetching the rest from the Store API with `DEFAULT_CONCURRENCY` requests
+/// in flight at once. Freshly fetched entries are written back into
+/// `disk_cache` (the caller is responsible for calling `save`).
+pub async fn resolve_all(
+    app_ids: &[u32],
+    appinfo_cache: &HashMap<u32, String>,
+    disk_cache: &mut DiskCache,
+) -> HashMap<u32, (bool, String)> {
+    let mut resolved = HashMap::new();
+    let mut to_fetch = Vec::new();
+
+    for &app_id in app_ids {
+        if let Some(name) = appinfo_cache.get(&app_id) {
+            resolved.insert(app_id, (true, name.clone()));
+        } else if let Some(cached) = disk_cache.get_fresh(app_id) {
+            resolved.insert(app_id, cached);
+        } else {
+            to_fetch.push(app_id);
+        }
+    }
+
+    let limiter = RateLimiter::new(RATE_LIMIT_PER_SEC);
+    let semaphore = Semaphore::new(DEFAULT_CONCURRENCY);
+
+    let fetched: Vec<(u32, Option<(bool, String)>)> = stream::iter(to_fetch)
+        .map(|app_id| {
+            let limiter = &limiter;
+            let semaphore = &semaphore;
+            async move {
+                let _permit = semaphore.acquire().await.unwrap();
+                fetch_one(app_id, limiter).await
+            }
+        })
+        .buffer_unordered(DEFAULT_CONCURRENCY)
+        .collect()
+        .await;
+
+    for (app_id, result) in fetched {
+        if let Some((success, name)) = &result {
+            disk_cache.insert(app_id, *success, name.clone());
+        }
+        if let Some(value) = result {
+            resolved.insert(app_id, value);
+        }
+    }
+
+    resolved
+}
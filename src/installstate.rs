@@ -0,0 +1,162 @@
+//! Per-app install state derived from `steamapps/appmanifest_<id>.acf`
+//! `StateFlags`, replacing the old binary INSTALLED/NOT INSTALLED split
+//! that only looked at `libraryfolders.vdf`'s `apps` block.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const STATE_UNINSTALLED: u32 = 1;
+const STATE_UPDATE_REQUIRED: u32 = 2;
+const STATE_FULLY_INSTALLED: u32 = 4;
+const STATE_UPDATE_RUNNING: u32 = 256;
+const STATE_UPDATE_STARTED: u32 = 1024;
+const STATE_UNINSTALLING: u32 = 2048;
+
+/// The raw `StateFlags` bitfield Steam stores in an app's manifest.
+#[derive(Debug, Clone, Copy)]
+pub struct InstallState {
+    pub state_flags: u32,
+}
+
+impl InstallState {
+    /// True only when `describe()` would report "Fully Installed" — i.e.
+    /// `STATE_FULLY_INSTALLED` is set and none of the higher-priority
+    /// flags `describe()` checks first (uninstalling/updating) are.
+    pub fn is_fully_installed(&self) -> bool {
+        const HIGHER_PRIORITY: u32 =
+            STATE_UNINSTALLING | STATE_UPDATE_STARTED | STATE_UPDATE_RUNNING | STATE_UPDATE_REQUIRED;
+
+        self.state_flags & HIGHER_PRIORITY == 0 && self.state_flags & STATE_FULLY_INSTALLED != 0
+    }
+
+    /// A short, human-readable description matching how Steam itself
+    /// labels these states in its own library UI.
+    pub fn describe(&self) -> &'static str {
+        if self.state_flags & STATE_UNINSTALLING != 0 {
+            "Uninstalling"
+        } else if self.state_flags & STATE_UPDATE_STARTED != 0 {
+            "Update Started"
+        } else if self.state_flags & STATE_UPDATE_RUNNING != 0 {
+            "Update Running"
+        } else if self.state_flags & STATE_UPDATE_REQUIRED != 0 {
+            "Update Required"
+        } else if self.state_flags & STATE_FULLY_INSTALLED != 0 {
+            "Fully Installed"
+        } else if self.state_flags & STATE_UNINSTALLED != 0 {
+            "Uninstalled"
+        } else {
+            "Unknown"
+        }
+    }
+}
+
+/// Parses every `appmanifest_<id>.acf` under `steamapps_path` and returns a
+/// map of appid -> `InstallState`.
+pub fn parse_appmanifests(steamapps_path: &Path) -> HashMap<u32, InstallState> {
+    let mut states = HashMap::new();
+
+    let entries = match fs::read_dir(steamapps_path) {
+        Ok(entries) => entries,
+        Err(_) => return states,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let file_name = match entry.file_name().into_string() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+
+        if !file_name.starts_with("appmanifest_") || !file_name.ends_with(".acf") {
+            continue;
+        }
+
+        let app_id: u32 = match file_name["appmanifest_".len()..file_name.len() - ".acf".len()]
+            .parse()
+        {
+            Ok(app_id) => app_id,
+            Err(_) => continue,
+        };
+
+        if let Some(state) = parse_acf_state_flags(&entry.path()) {
+            states.insert(app_id, state);
+        }
+    }
+
+    states
+}
+
+fn parse_acf_state_flags(acf_path: &Path) -> Option<InstallState> {
+    let content = fs::read_to_string(acf_path).ok()?;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !trimmed.starts_with("\"StateFlags\"") {
+            continue;
+        }
+
+        let value = trimmed.split('"').nth(3)?;
+        let state_flags: u32 = value.parse().ok()?;
+        return Some(InstallState { state_flags });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fully_installed_is_green_only_state() {
+        let state = InstallState {
+            state_flags: STATE_FULLY_INSTALLED,
+        };
+        assert!(state.is_fully_installed());
+        assert_eq!(state.describe(), "Fully Installed");
+    }
+
+    #[test]
+    fn fully_installed_plus_update_required_is_not_fully_installed() {
+        // Steam sets both STATE_FULLY_INSTALLED and STATE_UPDATE_REQUIRED
+        // while an update is pending; describe() must report the update,
+        // and is_fully_installed() must agree (no green "Update Required").
+        let state = InstallState {
+            state_flags: STATE_FULLY_INSTALLED | STATE_UPDATE_REQUIRED,
+        };
+        assert!(!state.is_fully_installed());
+        assert_eq!(state.describe(), "Update Required");
+    }
+
+    #[test]
+    fn uninstalled_is_not_fully_installed() {
+        let state = InstallState {
+            state_flags: STATE_UNINSTALLED,
+        };
+        assert!(!state.is_fully_installed());
+        assert_eq!(state.describe(), "Uninstalled");
+    }
+
+    #[test]
+    fn parses_state_flags_from_synthetic_acf() {
+        let dir = std::env::temp_dir().join(format!("installstate_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let acf_path = dir.join("appmanifest_440.acf");
+        fs::write(
+            &acf_path,
+            r#""AppState"
+{
+    "appid"     "440"
+    "StateFlags"        "4"
+}
+"#,
+        )
+        .unwrap();
+
+        let state = parse_acf_state_flags(&acf_path);
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(state.is_some());
+        assert_eq!(state.unwrap().state_flags, 4);
+    }
+}
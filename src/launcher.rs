@@ -0,0 +1,193 @@
+//! Discovery of Wine/Proton prefixes belonging to non-Steam launchers, so
+//! the same "orphaned prefix" problem Steam has can be spotted for them
+//! too.
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A launcher this tool knows how to find compat prefixes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Launcher {
+    Steam,
+    Lutris,
+    Heroic,
+}
+
+impl Launcher {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Launcher::Steam => "Steam",
+            Launcher::Lutris => "Lutris",
+            Launcher::Heroic => "Heroic",
+        }
+    }
+}
+
+/// A single compat/Wine prefix discovered under a non-Steam launcher.
+#[derive(Debug)]
+pub struct LauncherPrefix {
+    pub launcher: Launcher,
+    pub name: String,
+    pub prefix_path: PathBuf,
+}
+
+/// Discovers prefixes for every supported non-Steam launcher under `home`.
+pub fn discover_all(home: &Path) -> Vec<LauncherPrefix> {
+    let mut prefixes = discover_lutris_prefixes(home);
+    prefixes.extend(discover_heroic_prefixes(home));
+    prefixes
+}
+
+/// Scans `~/.config/lutris/games/*.yml` for each game's configured Wine
+/// prefix. Lutris's per-game YAML is a simple `key: value` format with a
+/// nested `game:` block, so it's parsed with the same line-based approach
+/// used for Valve's VDF files rather than pulling in a YAML crate.
+fn discover_lutris_prefixes(home: &Path) -> Vec<LauncherPrefix> {
+    let games_dir = home.join(".config/lutris/games");
+    let mut prefixes = Vec::new();
+
+    let entries = match fs::read_dir(&games_dir) {
+        Ok(entries) => entries,
+        Err(_) => return prefixes,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("yml") {
+            continue;
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+
+        let mut name = None;
+        let mut prefix_path = None;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            if let Some(value) = trimmed.strip_prefix("name:") {
+                name = Some(value.trim().trim_matches('"').to_string());
+            }
+
+            if let Some(value) = trimmed.strip_prefix("prefix:") {
+                prefix_path = Some(PathBuf::from(value.trim().trim_matches('"')));
+            }
+        }
+
+        if let (Some(name), Some(prefix_path)) = (name, prefix_path) {
+            if prefix_path.exists() {
+                prefixes.push(LauncherPrefix {
+                    launcher: Launcher::Lutris,
+                    name,
+                    prefix_path,
+                });
+            }
+        }
+    }
+
+    prefixes
+}
+
+/// Reads Heroic's per-game settings under `~/.config/heroic/GamesConfig/`
+/// for a configured `winePrefix`, and resolves each game's display name
+/// from Heroic's GOG (`gog_store/installed.json`) and Epic/legendary
+/// (`legendary/installed.json`) install lists.
+fn discover_heroic_prefixes(home: &Path) -> Vec<LauncherPrefix> {
+    let mut prefixes = Vec::new();
+
+    let mut known_titles = load_heroic_titles(&home.join(".config/heroic/gog_store/installed.json"));
+    known_titles.extend(load_heroic_titles(
+        &home.join(".config/legendary/installed.json"),
+    ));
+
+    let games_config_dir = home.join(".config/heroic/GamesConfig");
+    let entries = match fs::read_dir(&games_config_dir) {
+        Ok(entries) => entries,
+        Err(_) => return prefixes,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let app_name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(stem) => stem.to_string(),
+            None => continue,
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+
+        let json: Value = match serde_json::from_str(&content) {
+            Ok(json) => json,
+            Err(_) => continue,
+        };
+
+        let wine_prefix = json
+            .get(&app_name)
+            .and_then(|settings| settings.get("winePrefix"))
+            .and_then(|value| value.as_str());
+
+        if let Some(wine_prefix) = wine_prefix {
+            let prefix_path = PathBuf::from(wine_prefix);
+            if prefix_path.exists() {
+                prefixes.push(LauncherPrefix {
+                    launcher: Launcher::Heroic,
+                    name: known_titles
+                        .get(&app_name)
+                        .cloned()
+                        .unwrap_or(app_name),
+                    prefix_path,
+                });
+            }
+        }
+    }
+
+    prefixes
+}
+
+/// Both Heroic's GOG store and legendary installed-game lists pair an
+/// opaque app/game id with a human-readable `title`, laid out differently
+/// in each file (see below).
+fn load_heroic_titles(path: &Path) -> std::collections::HashMap<String, String> {
+    let mut titles = std::collections::HashMap::new();
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return titles,
+    };
+
+    let json: Value = match serde_json::from_str(&content) {
+        Ok(json) => json,
+        Err(_) => return titles,
+    };
+
+    // legendary's installed.json is a map of appName -> {title, ...};
+    // Heroic's gog_store/installed.json is {"installed": [{appName, title?}, ...]}.
+    if let Some(installed) = json.get("installed").and_then(|v| v.as_array()) {
+        for game in installed {
+            if let (Some(app_name), Some(title)) = (
+                game.get("appName").and_then(|v| v.as_str()),
+                game.get("title").and_then(|v| v.as_str()),
+            ) {
+                titles.insert(app_name.to_string(), title.to_string());
+            }
+        }
+    } else if let Some(map) = json.as_object() {
+        for (app_name, game) in map {
+            if let Some(title) = game.get("title").and_then(|v| v.as_str()) {
+                titles.insert(app_name.clone(), title.to_string());
+            }
+        }
+    }
+
+    titles
+}
@@ -1,12 +1,18 @@
+mod appinfo;
+mod backup;
+mod cleanup;
+mod compattool;
+mod fetchcache;
+mod installstate;
+mod launcher;
+
 use colored::*;
+use installstate::InstallState;
 use lazy_static::lazy_static;
-use reqwest;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tokio;
 
 lazy_static! {
     static ref PROTON_VERSIONS: HashMap<u32, &'static str> = {
@@ -18,6 +24,7 @@ lazy_static! {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[allow(dead_code)]
 struct SteamAppInfo {
     appid: u32,
     name: String,
@@ -33,6 +40,7 @@ struct CompatData {
 struct SteamLibrary {
     path: PathBuf,
     installed_apps: HashSet<u32>,
+    install_states: HashMap<u32, InstallState>,
 }
 
 #[tokio::main]
@@ -54,13 +62,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         steam_path.display().to_string().blue()
     );
 
+    if let Some(args) = restore_subcommand_args() {
+        return run_restore(&steam_path, &args);
+    }
+
+    let appinfo_cache = appinfo::load_appinfo_cache(&steam_path);
+    println!(
+        "INFO: Loaded {} app names from local appinfo.vdf cache.",
+        appinfo_cache.len()
+    );
+
+    let compat_tool_mapping =
+        compattool::parse_compat_tool_mapping(&steam_path.join("config/config.vdf"));
+    let custom_tools = compattool::discover_custom_tools(&steam_path);
+    println!(
+        "INFO: Found {} custom compat tools under compatibilitytools.d/.",
+        custom_tools.len()
+    );
+
     let libraries = get_steam_libraries(&steam_path)?;
     println!("INFO: Found {} Steam libraries.", libraries.len());
 
     let mut all_installed_apps: HashSet<u32> = HashSet::new();
+    let mut all_install_states: HashMap<u32, InstallState> = HashMap::new();
     for library in &libraries {
         println!("INFO: Processing library at: {}", library.path.display());
         all_installed_apps.extend(&library.installed_apps);
+        all_install_states.extend(&library.install_states);
     }
 
     println!("{}", "Analyzing compatdata directories...".bold());
@@ -74,23 +102,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         all_compatdata.extend(scan_compatdata_dirs(&library.path));
     }
 
+    let proton_app_ids: HashSet<u32> = PROTON_VERSIONS.keys().copied().collect();
+
+    let mut known_names = appinfo_cache.clone();
+    for (&app_id, name) in PROTON_VERSIONS.iter() {
+        known_names.insert(app_id, name.to_string());
+    }
+
+    let app_ids: Vec<u32> = all_compatdata.iter().map(|entry| entry.app_id).collect();
+    let mut disk_cache = fetchcache::DiskCache::load();
+    let resolved = fetchcache::resolve_all(&app_ids, &known_names, &mut disk_cache).await;
+    disk_cache.save();
+
+    let compat_tool_by_id: HashMap<u32, String> = all_compatdata
+        .iter()
+        .map(|entry| {
+            let tool = compattool::read_prefix_config_info(&entry.path)
+                .or_else(|| compat_tool_mapping.get(&entry.app_id).cloned())
+                .unwrap_or_else(|| "unknown".to_string());
+            (entry.app_id, tool)
+        })
+        .collect();
+
+    let cli_options = cleanup::parse_args();
+
+    if cli_options.prune || cli_options.dry_run {
+        let orphans = cleanup::find_orphaned_prefixes(
+            &all_compatdata,
+            &all_installed_apps,
+            &proton_app_ids,
+            &resolved,
+            &compat_tool_by_id,
+        );
+        cleanup::print_orphan_report(&orphans);
+
+        if cli_options.prune && !cli_options.dry_run {
+            cleanup::delete_orphans(&orphans, cli_options.backup_dir.as_ref())?;
+        }
+    }
+
     for entry in all_compatdata {
         let app_id = entry.app_id;
-        let is_installed = all_installed_apps.contains(&app_id);
         let is_proton = PROTON_VERSIONS.contains_key(&app_id);
 
         if is_proton {
             proton_versions_found.insert(app_id);
         }
 
-        match fetch_app_info(app_id).await {
-            Some((success, name)) => {
-                let status = if is_installed {
-                    "INSTALLED".green()
-                } else {
-                    "NOT INSTALLED".yellow()
-                };
+        let compat_tool = compat_tool_by_id
+            .get(&app_id)
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let status = match all_install_states.get(&app_id) {
+            Some(state) if state.is_fully_installed() => state.describe().green(),
+            Some(state) => state.describe().yellow(),
+            None => "NOT INSTALLED".yellow(),
+        };
 
+        match resolved.get(&app_id).cloned() {
+            Some((success, name)) => {
                 let app_status = if success {
                     if is_proton {
                         name.cyan()
@@ -102,28 +173,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 };
 
                 println!(
-                    "AppID {:6} | {:<50} | {}",
+                    "AppID {:6} | {:<50} | {} | tool: {:<20} | launcher: {}",
                     app_id.to_string().blue(),
                     app_status,
-                    status
+                    status,
+                    compat_tool.magenta(),
+                    launcher::Launcher::Steam.label().blue()
                 );
             }
             None => {
                 println!(
-                    "AppID {:6} | {:<50} | {}",
+                    "AppID {:6} | {:<50} | {} | tool: {:<20} | launcher: {}",
                     app_id.to_string().blue(),
                     "Failed to fetch app info".red(),
-                    if is_installed {
-                        "INSTALLED".green()
-                    } else {
-                        "NOT INSTALLED".yellow()
-                    }
+                    status,
+                    compat_tool.magenta(),
+                    launcher::Launcher::Steam.label().blue()
                 );
             }
         }
+    }
 
-        // Add a small delay to avoid hitting Steam's rate limits.
-        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let other_launcher_prefixes = launcher::discover_all(Path::new(&home));
+    if !other_launcher_prefixes.is_empty() {
+        println!("\n{}", "Other Launcher Prefixes Found:".bold().cyan());
+        println!("{}", "===============================".bold());
+
+        for entry in &other_launcher_prefixes {
+            println!(
+                "{:<50} | launcher: {:<8} | {}",
+                entry.name,
+                entry.launcher.label().blue(),
+                entry.prefix_path.display()
+            );
+        }
     }
 
     if !proton_versions_found.is_empty() {
@@ -137,6 +220,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    if !custom_tools.is_empty() {
+        println!("\n{}", "Custom Compat Tools Found:".bold().cyan());
+        println!("{}", "==========================".bold());
+
+        for (internal_name, display_name) in &custom_tools {
+            println!("{:<25} | {}", internal_name.cyan(), display_name);
+        }
+    }
+
     println!("\n{}", "Analysis complete!".bold().green());
     Ok(())
 }
@@ -145,10 +237,7 @@ fn get_steam_libraries(steam_path: &Path) -> Result<Vec<SteamLibrary>, Box<dyn s
     let mut libraries = Vec::new();
 
     // Add the main Steam library.
-    libraries.push(SteamLibrary {
-        path: steam_path.to_path_buf(),
-        installed_apps: parse_installed_apps(&steam_path.join("steamapps/libraryfolders.vdf"))?,
-    });
+    libraries.push(build_steam_library(steam_path.to_path_buf()));
 
     // Parse libraryfolders.vdf to find additional libraries.
     let content = fs::read_to_string(steam_path.join("steamapps/libraryfolders.vdf"))?;
@@ -169,13 +258,7 @@ fn get_steam_libraries(steam_path: &Path) -> Result<Vec<SteamLibrary>, Box<dyn s
             let path = current_path.take().unwrap();
 
             if path.exists() && path != steam_path {
-                libraries.push(SteamLibrary {
-                    installed_apps: parse_installed_apps(
-                        &path.join("steamapps/libraryfolders.vdf"),
-                    )
-                    .unwrap_or_else(|_| HashSet::new()),
-                    path,
-                });
+                libraries.push(build_steam_library(path));
             }
         }
     }
@@ -183,73 +266,18 @@ fn get_steam_libraries(steam_path: &Path) -> Result<Vec<SteamLibrary>, Box<dyn s
     Ok(libraries)
 }
 
-fn parse_installed_apps(config_path: &Path) -> Result<HashSet<u32>, Box<dyn std::error::Error>> {
-    let mut installed_apps = HashSet::new();
-    let mut in_apps_section = false;
-
-    if let Ok(content) = fs::read_to_string(config_path) {
-        for line in content.lines() {
-            let trimmed_line = line.trim();
-
-            if trimmed_line == "\"apps\"" {
-                in_apps_section = true;
-                continue;
-            }
-
-            if in_apps_section && trimmed_line == "}" {
-                in_apps_section = false;
-                continue;
-            }
-
-            if in_apps_section && trimmed_line.starts_with('"') {
-                if let Some(app_id_str) = trimmed_line.split('"').nth(1) {
-                    if let Ok(app_id) = app_id_str.parse::<u32>() {
-                        installed_apps.insert(app_id);
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(installed_apps)
-}
-
-async fn fetch_app_info(app_id: u32) -> Option<(bool, String)> {
-    // First check if this is a known Proton version.
-    if let Some(proton_name) = PROTON_VERSIONS.get(&app_id) {
-        return Some((true, proton_name.to_string()));
-    }
-
-    let url = format!(
-        "https://store.steampowered.com/api/appdetails?appids={}",
-        app_id
-    );
-
-    println!("Fetched app info for {} and got {}.", app_id, url);
-
-    match reqwest::get(&url).await {
-        Ok(response) => {
-            if let Ok(text) = response.text().await {
-                if let Ok(json) = serde_json::from_str::<Value>(&text) {
-                    if let Some(app_data) = json.get(&app_id.to_string()) {
-                        let success = app_data["success"].as_bool().unwrap_or(false);
-                        let name = if success {
-                            app_data["data"]["name"]
-                                .as_str()
-                                .unwrap_or("Unknown")
-                                .to_string()
-                        } else {
-                            "Unknown Application".to_string()
-                        };
-
-                        return Some((success, name));
-                    }
-                }
-            }
-
-            None
-        }
-        Err(_) => None,
+/// Builds a `SteamLibrary` by reading each `appmanifest_<id>.acf` under the
+/// library's `steamapps/` directory, rather than trusting the `"apps"`
+/// block in `libraryfolders.vdf` (which only lists presence, not whether an
+/// app is actually fully installed).
+fn build_steam_library(path: PathBuf) -> SteamLibrary {
+    let install_states = installstate::parse_appmanifests(&path.join("steamapps"));
+    let installed_apps = install_states.keys().copied().collect();
+
+    SteamLibrary {
+        path,
+        installed_apps,
+        install_states,
     }
 }
 
@@ -274,3 +302,29 @@ fn scan_compatdata_dirs(steam_path: &Path) -> Vec<CompatData> {
 
     compat_entries
 }
+
+/// Returns `Some(archive_path)` if invoked as `restore <archive.tar.gz>`.
+fn restore_subcommand_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    if args.next()?.as_str() != "restore" {
+        return None;
+    }
+    args.next()
+}
+
+fn run_restore(steam_path: &Path, archive_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let compatdata_root = steam_path.join("steamapps/compatdata");
+
+    match backup::restore_prefix(Path::new(archive_path), &compatdata_root)? {
+        Some(manifest) => println!(
+            "{} AppID {} ({}) using {}",
+            "Restored".bold().green(),
+            manifest.app_id.to_string().blue(),
+            manifest.name,
+            manifest.compat_tool.magenta()
+        ),
+        None => println!("Aborted, prefix was not restored."),
+    }
+
+    Ok(())
+}